@@ -0,0 +1,37 @@
+//! Screen capture backend abstraction used by the live streaming subsystem.
+//! Kept separate from the streaming loop so platform-specific capture code
+//! doesn't leak into the frame pacing / diffing logic.
+//!
+//! Scope note: this module ships the [`CaptureBackend`] trait and the types around it only.
+//! Platform implementations (xdg-desktop-portal/pipewire on Linux, ScreenCaptureKit on macOS,
+//! Windows.Graphics.Capture on Windows) are real integrations against external services and are
+//! deferred to their own follow-up requests rather than bundled in here — don't read "streaming
+//! support landed" as "a capture backend ships out of the box".
+
+use crate::types::Zoom65Error;
+
+/// A rectangular region of the screen to capture, in screen-space pixels
+#[derive(Debug, Clone, Copy)]
+pub struct Region {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// A single captured frame, packed as 8-bit RGBA rows
+pub struct Frame {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+/// Platform screen capture backend. One instance captures repeated frames of the same region.
+///
+/// No built-in backend ships yet (the xdg-desktop-portal/pipewire handshake needed for a real
+/// Linux implementation isn't wired up) — callers must supply their own via
+/// [`Zoom65v3::stream_with_backend`](crate::Zoom65v3::stream_with_backend) until one does.
+pub trait CaptureBackend {
+    /// Grab a single frame of `region`
+    fn capture(&mut self, region: Region) -> Result<Frame, Zoom65Error>;
+}
@@ -0,0 +1,35 @@
+//! CRC-32 checksum used to validate command payloads sent to the device
+
+/// Compute the checksum the firmware expects for a given payload
+pub fn checksum(data: &[u8]) -> [u8; 4] {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    (!crc).to_be_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_known_crc32_test_vector() {
+        // The standard CRC-32/ISO-HDLC check value for the ASCII string "123456789"
+        assert_eq!(checksum(b"123456789"), 0xCBF4_3926u32.to_be_bytes());
+    }
+
+    #[test]
+    fn empty_input_is_the_identity_value() {
+        assert_eq!(checksum(&[]), 0u32.to_be_bytes());
+    }
+
+    #[test]
+    fn different_payloads_produce_different_checksums() {
+        assert_ne!(checksum(b"abc"), checksum(b"abd"));
+    }
+}
@@ -0,0 +1,74 @@
+//! Protocol constants for the zoom65v3 device
+
+/// Meletrix Zoom65 v3 vendor id
+pub const ZOOM65_VENDOR_ID: u16 = 0x3151;
+/// Meletrix Zoom65 v3 product id
+pub const ZOOM65_PRODUCT_ID: u16 = 0x4002;
+/// HID usage page the screen module listens on
+pub const ZOOM65_USAGE_PAGE: u16 = 0xff00;
+/// HID usage the screen module listens on
+pub const ZOOM65_USAGE: u16 = 0x01;
+
+/// Version-keyed command maps. Each known firmware version gets its own set of method-id byte
+/// sequences and protocol quirks, selected at `open()` time. `open()` rejects a version with no
+/// listed map; `open_with_profile` and `open_forced` fall back to the closest known map.
+pub mod commands {
+    /// Method ids and protocol quirks for a specific firmware version
+    #[derive(Debug, Clone, Copy)]
+    pub struct CommandMap {
+        pub version_cmd: [u8; 4],
+        pub screen_up: [u8; 2],
+        pub screen_down: [u8; 2],
+        pub screen_switch: [u8; 2],
+        pub reset_screen: [u8; 2],
+        pub set_time: [u8; 2],
+        pub set_weather: [u8; 2],
+        pub set_sysinfo: [u8; 2],
+        pub upload_start: [u8; 2],
+        pub upload_length: [u8; 2],
+        pub upload_end: [u8; 2],
+        pub image_delete: [u8; 2],
+        pub gif_delete: [u8; 2],
+        /// Read back the stored length and checksum of the last upload, to verify it landed intact
+        pub verify: [u8; 2],
+        /// Whether channel-2 (gif) uploads need 32-bit alignment padding on the final chunk
+        pub gif_padding: bool,
+    }
+
+    /// The baseline command map, shared by every known firmware version so far
+    pub const BASE: CommandMap = CommandMap {
+        version_cmd: [0x0, 0x01, 0xa5, 0x00],
+        screen_up: [0xa5, 0x01],
+        screen_down: [0xa5, 0x02],
+        screen_switch: [0xa5, 0x03],
+        reset_screen: [0xa5, 0x04],
+        set_time: [0xa5, 0x05],
+        set_weather: [0xa5, 0x06],
+        set_sysinfo: [0xa5, 0x07],
+        upload_start: [0xa5, 0x08],
+        upload_length: [0xa5, 0x09],
+        upload_end: [0xa5, 0x0a],
+        image_delete: [0xa5, 0x0b],
+        gif_delete: [0xa5, 0x0c],
+        verify: [0xa5, 0x0d],
+        gif_padding: true,
+    };
+
+    /// Firmware versions known to speak this protocol, and the command map to use for each
+    pub const KNOWN_PROFILES: &[(u8, CommandMap)] = &[(1, BASE), (2, BASE), (3, BASE)];
+
+    /// Look up the command map for an exact firmware version
+    pub fn profile_for(version: u8) -> Option<CommandMap> {
+        KNOWN_PROFILES.iter().find(|(v, _)| *v == version).map(|(_, map)| *map)
+    }
+
+    /// Fall back to the closest known profile (by version number) for an unlisted firmware
+    /// version, so users on newer-but-compatible firmware aren't locked out
+    pub fn closest_profile(version: u8) -> CommandMap {
+        KNOWN_PROFILES
+            .iter()
+            .min_by_key(|(v, _)| v.abs_diff(version))
+            .map(|(_, map)| *map)
+            .unwrap_or(BASE)
+    }
+}
@@ -0,0 +1,106 @@
+//! Long-running daemon that periodically refreshes the keyboard's time,
+//! weather and system info on independently configurable intervals,
+//! instead of relying on external cron-style invocation. Paired with the
+//! [`crate::metrics`] periodic logger for aggregated status output.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use chrono::Local;
+
+use crate::Zoom65v3;
+use crate::metrics;
+use crate::types::{Icon, Zoom65Error};
+
+/// Interval configuration for each independently refreshed field, plus the metrics logger tick
+pub struct DaemonConfig {
+    pub time_interval: Duration,
+    pub weather_interval: Duration,
+    pub sysinfo_interval: Duration,
+    pub log_interval: Duration,
+}
+
+/// A source of weather data for the daemon to push on its interval
+pub trait WeatherSource: Send + 'static {
+    fn weather(&mut self) -> Result<(Icon, u8, u8, u8), Zoom65Error>;
+}
+
+/// A source of system info for the daemon to push on its interval
+pub trait SystemInfoSource: Send + 'static {
+    fn system_info(&mut self) -> Result<(u8, u8, f32), Zoom65Error>;
+}
+
+/// Handle used to stop a running daemon and its paired metrics logger
+pub struct DaemonHandle {
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<Zoom65v3>,
+    _logger: metrics::LoggerHandle,
+}
+
+impl DaemonHandle {
+    /// Signal the daemon to stop and wait for it to exit, returning the device for further use
+    pub fn stop(self) -> Zoom65v3 {
+        self.stop.store(true, Ordering::Relaxed);
+        self.thread.join().expect("daemon thread panicked")
+    }
+}
+
+/// Spawn the daemon: a single ticker thread that refreshes time, weather and system info on
+/// their configured intervals, plus a paired [`metrics::spawn_logger`] thread, until
+/// [`DaemonHandle::stop`] is called.
+pub fn spawn(
+    mut device: Zoom65v3,
+    config: DaemonConfig,
+    mut weather: impl WeatherSource,
+    mut sysinfo: impl SystemInfoSource,
+) -> DaemonHandle {
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_clone = stop.clone();
+    let logger = metrics::spawn_logger(config.log_interval);
+
+    let thread = thread::spawn(move || {
+        // `None` means "due immediately" — seeding these with `Instant::now() - interval`
+        // instead would panic via `Instant`'s checked subtraction whenever the configured
+        // interval exceeds how long the process/monotonic clock has been running.
+        let mut last_time: Option<Instant> = None;
+        let mut last_weather: Option<Instant> = None;
+        let mut last_sysinfo: Option<Instant> = None;
+
+        while !stop_clone.load(Ordering::Relaxed) {
+            let now = Instant::now();
+
+            if last_time.is_none_or(|t| now.duration_since(t) >= config.time_interval) {
+                if device.set_time(Local::now()).is_err() {
+                    metrics::METRICS.record_failure();
+                }
+                last_time = Some(now);
+            }
+            if last_weather.is_none_or(|t| now.duration_since(t) >= config.weather_interval) {
+                let result = weather.weather().and_then(|(icon, current, low, high)| {
+                    device.set_weather(icon, current, low, high)
+                });
+                if result.is_err() {
+                    metrics::METRICS.record_failure();
+                }
+                last_weather = Some(now);
+            }
+            if last_sysinfo.is_none_or(|t| now.duration_since(t) >= config.sysinfo_interval) {
+                let result = sysinfo.system_info().and_then(|(cpu_temp, gpu_temp, download_rate)| {
+                    device.set_system_info(cpu_temp, gpu_temp, download_rate)
+                });
+                if result.is_err() {
+                    metrics::METRICS.record_failure();
+                }
+                last_sysinfo = Some(now);
+            }
+
+            thread::sleep(Duration::from_millis(200));
+        }
+
+        device
+    });
+
+    DaemonHandle { stop, thread, _logger: logger }
+}
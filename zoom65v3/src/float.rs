@@ -0,0 +1,40 @@
+//! Minimal reduced-precision float encoding used by the system info command
+
+/// A 2-byte fixed point format the firmware uses in place of a real float
+pub struct DumbFloat16(u16);
+
+impl DumbFloat16 {
+    /// Construct from a standard f32, quantizing to the firmware's fixed point format.
+    /// Values are clamped to the representable range before encoding.
+    pub fn new(value: f32) -> Self {
+        let clamped = value.clamp(0.0, 255.99);
+        let whole = clamped.trunc() as u16;
+        let frac = (clamped.fract() * 100.0) as u16;
+        Self((whole << 8) | (frac & 0xff))
+    }
+
+    /// Encode into the 2-byte wire representation the firmware expects
+    pub fn to_bit_repr(&self) -> [u8; 2] {
+        self.0.to_be_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_whole_and_fractional_parts_into_separate_bytes() {
+        assert_eq!(DumbFloat16::new(12.5).to_bit_repr(), [12, 50]);
+    }
+
+    #[test]
+    fn clamps_negative_values_to_zero() {
+        assert_eq!(DumbFloat16::new(-5.0).to_bit_repr(), [0, 0]);
+    }
+
+    #[test]
+    fn clamps_values_above_the_representable_range() {
+        assert_eq!(DumbFloat16::new(1000.0).to_bit_repr(), [255, 99]);
+    }
+}
@@ -1,12 +1,12 @@
 //! High level hidapi abstraction for interacting with zoom65v3 screen modules
 
-use std::io::{stdout, Write};
 use std::ops::Deref;
+use std::path::Path;
 use std::sync::{LazyLock, RwLock};
 
 use checksum::checksum;
 use chrono::{DateTime, Datelike, TimeZone, Timelike};
-use consts::commands;
+use consts::commands::CommandMap;
 use float::DumbFloat16;
 use hidapi::{HidApi, HidDevice};
 use types::ScreenPosition;
@@ -16,47 +16,119 @@ use crate::types::{Icon, Zoom65Error};
 pub mod checksum;
 pub mod consts;
 pub mod float;
+pub mod capture;
+pub mod daemon;
+pub mod media;
+pub mod metrics;
+pub mod preview;
+pub mod stream;
 pub mod types;
 
 /// Lazy handle to hidapi
 static API: LazyLock<RwLock<HidApi>> =
     LazyLock::new(|| RwLock::new(HidApi::new().expect("failed to init hidapi")));
 
+/// Progress of an `upload_media` call interrupted after exhausting its per-chunk retries,
+/// scoped to the exact channel and buffer it belongs to so a later call can tell whether it's
+/// safe to resume from `chunk` or whether it must restart from zero
+struct ResumeState {
+    channel: u8,
+    len: usize,
+    checksum: [u8; 4],
+    chunk: usize,
+}
+
 /// High level abstraction for managing a zoom65 v3 keyboard
 pub struct Zoom65v3 {
     device: HidDevice,
     buf: [u8; 64],
+    /// Method ids and quirks for the detected (or overridden) firmware version
+    profile: CommandMap,
+    /// Whether to render a kitty graphics protocol preview before path-based uploads
+    preview: bool,
+    /// State of the last interrupted upload, if any, used to resume `upload_media`
+    resume: Option<ResumeState>,
 }
 
 impl Zoom65v3 {
-    /// Find and open the device for modifications
-    pub fn open() -> Result<Self, Zoom65Error> {
+    fn find_and_open() -> Result<(HidDevice, [u8; 64]), Zoom65Error> {
         API.write().unwrap().refresh_devices()?;
         let api = API.read().unwrap();
+        let device = api
+            .device_list()
+            .find(|d| {
+                d.vendor_id() == consts::ZOOM65_VENDOR_ID
+                    && d.product_id() == consts::ZOOM65_PRODUCT_ID
+                    && d.usage_page() == consts::ZOOM65_USAGE_PAGE
+                    && d.usage() == consts::ZOOM65_USAGE
+            })
+            .ok_or(Zoom65Error::DeviceNotFound)?
+            .open_device(&api)?;
+        Ok((device, [0u8; 64]))
+    }
+
+    /// Find and open the device for modifications, rejecting unrecognized firmware versions
+    pub fn open() -> Result<Self, Zoom65Error> {
+        Self::open_with_profile(None)
+    }
+
+    /// Open the device, optionally overriding the detected firmware version. Pass
+    /// `Some(version)` to use the closest known command profile for that specific version
+    /// instead of auto-detecting — this is what lets a user on unlisted-but-working firmware
+    /// proceed instead of being locked out, without resorting to `open_forced`'s blanket bypass.
+    /// Pass `None` to behave like `open`, which still hard-rejects a detected version with no
+    /// listed profile.
+    pub fn open_with_profile(version_override: Option<u8>) -> Result<Self, Zoom65Error> {
+        let (device, buf) = Self::find_and_open()?;
         let mut this = Self {
-            device: api
-                .device_list()
-                .find(|d| {
-                    d.vendor_id() == consts::ZOOM65_VENDOR_ID
-                        && d.product_id() == consts::ZOOM65_PRODUCT_ID
-                        && d.usage_page() == consts::ZOOM65_USAGE_PAGE
-                        && d.usage() == consts::ZOOM65_USAGE
-                })
-                .ok_or(Zoom65Error::DeviceNotFound)?
-                .open_device(&api)?,
-            buf: [0u8; 64],
+            device,
+            buf,
+            profile: consts::commands::BASE,
+            preview: false,
+            resume: None,
         };
 
-        if !consts::APPROVED_VERSIONS.contains(&this.get_version()?) {
-            return Err(Zoom65Error::UnknownFirmwareVersion);
-        }
+        this.profile = match version_override {
+            Some(version) => consts::commands::closest_profile(version),
+            None => {
+                let version = this.get_version()?;
+                consts::commands::profile_for(version).ok_or(Zoom65Error::UnknownFirmwareVersion)?
+            },
+        };
         Ok(this)
     }
 
+    /// Open the device against the closest known command profile even if the detected firmware
+    /// version isn't explicitly listed, bypassing the rejection in [`open`](Self::open).
+    ///
+    /// # Safety
+    /// Sending commands built for the wrong firmware version can put the screen controller into
+    /// an undefined state. Only use this after manually confirming the unlisted firmware behaves
+    /// compatibly with a known profile.
+    pub unsafe fn open_forced() -> Result<Self, Zoom65Error> {
+        let (device, buf) = Self::find_and_open()?;
+        let mut this = Self {
+            device,
+            buf,
+            profile: consts::commands::BASE,
+            preview: false,
+            resume: None,
+        };
+        let version = this.get_version()?;
+        this.profile = consts::commands::closest_profile(version);
+        Ok(this)
+    }
+
+    /// Enable or disable the kitty graphics protocol preview for path-based uploads.
+    /// Has no visible effect on terminals that don't advertise kitty support.
+    pub fn set_preview(&mut self, enabled: bool) {
+        self.preview = enabled;
+    }
+
     /// Get the version id tracked by the web driver
     pub fn get_version(&mut self) -> Result<u8, Zoom65Error> {
         // Write to device and read response
-        self.device.write(&consts::commands::ZOOM65_VERSION_CMD)?;
+        self.device.write(&self.profile.version_cmd)?;
         let len = self.device.read(&mut self.buf)?;
         let slice = &self.buf[..len];
         assert!(slice[0] == 1);
@@ -92,28 +164,28 @@ impl Zoom65v3 {
     /// Increment the screen position
     #[inline(always)]
     pub fn screen_up(&mut self) -> Result<(), Zoom65Error> {
-        self.update(commands::ZOOM65_SCREEN_UP, &[])?;
+        self.update(self.profile.screen_up, &[])?;
         Ok(())
     }
 
     /// Decrement the screen position
     #[inline(always)]
     pub fn screen_down(&mut self) -> Result<(), Zoom65Error> {
-        self.update(commands::ZOOM65_SCREEN_DOWN, &[])?;
+        self.update(self.profile.screen_down, &[])?;
         Ok(())
     }
 
     /// Switch the active screen
     #[inline(always)]
     pub fn screen_switch(&mut self) -> Result<(), Zoom65Error> {
-        self.update(commands::ZOOM65_SCREEN_SWITCH, &[])?;
+        self.update(self.profile.screen_switch, &[])?;
         Ok(())
     }
 
     /// Reset the screen back to the meletrix logo
     #[inline(always)]
     pub fn reset_screen(&mut self) -> Result<(), Zoom65Error> {
-        self.update(commands::ZOOM65_RESET_SCREEN_ID, &[])?;
+        self.update(self.profile.reset_screen, &[])?;
         Ok(())
     }
 
@@ -150,7 +222,7 @@ impl Zoom65v3 {
     /// Update the keyboards current time
     pub fn set_time<Tz: TimeZone>(&mut self, time: DateTime<Tz>) -> Result<(), Zoom65Error> {
         self.update(
-            commands::ZOOM65_SET_TIME_ID,
+            self.profile.set_time,
             &[
                 // Provide the current year without the century.
                 // This prevents overflows on the year 2256 (meletrix web ui just subtracts 2000)
@@ -174,7 +246,7 @@ impl Zoom65v3 {
         high: u8,
     ) -> Result<(), Zoom65Error> {
         self.update(
-            commands::ZOOM65_SET_WEATHER_ID,
+            self.profile.set_weather,
             &[icon as u8, current, low, high],
         )?;
         Ok(())
@@ -189,29 +261,40 @@ impl Zoom65v3 {
     ) -> Result<(), Zoom65Error> {
         let bytes = DumbFloat16::new(download_rate).to_bit_repr();
         self.update(
-            commands::ZOOM65_SET_SYSINFO_ID,
+            self.profile.set_sysinfo,
             &[cpu_temp, gpu_temp, bytes[0], bytes[1]],
         )?;
         Ok(())
     }
 
+    /// Maximum number of retries for a single chunk before aborting the upload
+    const CHUNK_MAX_RETRIES: u32 = 3;
+
     fn upload_media(&mut self, buf: impl AsRef<[u8]>, channel: u8) -> Result<(), Zoom65Error> {
         let image = buf.as_ref();
+        let image_checksum = checksum(image);
+
+        // Only resume from a prior failure if it was interrupted partway through this exact
+        // channel and buffer; otherwise a stale resume index would skip chunks of (or the
+        // start-of-upload handshake for) an unrelated upload, corrupting it. Restart instead.
+        let resume_from = match self.resume.take() {
+            Some(state)
+                if state.channel == channel && state.len == image.len() && state.checksum == image_checksum =>
+            {
+                state.chunk
+            },
+            _ => 0,
+        };
 
-        // start upload
-        self.update(commands::ZOOM65_UPLOAD_START_ID, &[channel])?;
-        self.update(
-            commands::ZOOM65_UPLOAD_LENGTH,
-            &(image.len() as u32).to_be_bytes(),
-        )?;
-
-        let len = image.len();
-        let total = len / 24;
-        let width = total.to_string().len();
-        for (i, chunk) in image.chunks(24).enumerate() {
-            print!("\ruploading {len} bytes ({i:width$}/{total}) ... ");
-            stdout().flush().unwrap();
+        if resume_from == 0 {
+            self.update(self.profile.upload_start, &[channel])?;
+            self.update(
+                self.profile.upload_length,
+                &(image.len() as u32).to_be_bytes(),
+            )?;
+        }
 
+        for (i, chunk) in image.chunks(24).enumerate().skip(resume_from) {
             let chunk_len = chunk.len();
             let mut buf = [0u8; 33];
 
@@ -227,7 +310,7 @@ impl Zoom65v3 {
 
             // compute checksum
             let mut offset = 3 + 2 + chunk_len;
-            if channel == 2 && i == image.len() / 24 {
+            if channel == 2 && self.profile.gif_padding && i == image.len() / 24 {
                 // compute padding for final payload, the checksum needs 32-bit alignment
                 let padding = (4 - (image.len() % 24) % 4) % 4;
                 buf[2] += padding as u8;
@@ -237,23 +320,60 @@ impl Zoom65v3 {
             let crc = checksum(data);
             buf[offset..offset + 4].copy_from_slice(&crc);
 
-            // send payload and read response
-            self.write(&buf)?;
-            let len = self.device.read(&mut self.buf)?;
-            let slice = &self.buf[0..len];
+            // send payload and read response, retrying this chunk in place on a rejected response
+            let mut attempt = 0;
+            loop {
+                self.write(&buf)?;
+                let len = self.device.read(&mut self.buf)?;
+                let slice = &self.buf[0..len];
 
-            if slice[1] != 1 || slice[2] != 1 {
-                println!("\n{buf:?} -> \n{slice:?}");
-                return Err(Zoom65Error::UpdateCommandFailed);
+                if slice[1] == 1 && slice[2] == 1 {
+                    break;
+                }
+
+                attempt += 1;
+                if attempt > Self::CHUNK_MAX_RETRIES {
+                    self.resume = Some(ResumeState {
+                        channel,
+                        len: image.len(),
+                        checksum: image_checksum,
+                        chunk: i,
+                    });
+                    metrics::METRICS.record_failure();
+                    eprintln!("\n{buf:?} -> \n{slice:?}");
+                    return Err(Zoom65Error::UpdateCommandFailed);
+                }
+                metrics::METRICS.record_retry();
             }
         }
 
-        self.update(commands::ZOOM65_UPLOAD_END, &[1])?;
+        self.update(self.profile.upload_end, &[1])?;
+        self.verify_upload(channel, image.len(), image_checksum)?;
         // TODO: is this required?
         self.reset_screen()?;
 
-        println!("done");
+        self.resume = None;
+        metrics::METRICS.record_upload(image.len() as u64);
+
+        Ok(())
+    }
+
+    /// Read back the stored length and checksum for `channel` and confirm they match what was sent
+    fn verify_upload(&mut self, channel: u8, len: usize, expected_crc: [u8; 4]) -> Result<(), Zoom65Error> {
+        let response = self.update(self.profile.verify, &[channel])?;
+
+        // A short response is itself a mismatch, not a bug to panic on — firmware that doesn't
+        // actually support this (unverified) method id may ack with fewer bytes than expected.
+        if response.len() < 11 {
+            return Err(Zoom65Error::VerifyMismatch);
+        }
+
+        let stored_len = u32::from_be_bytes(response[3..7].try_into().unwrap());
+        let stored_crc = &response[7..11];
 
+        if stored_len as usize != len || stored_crc != expected_crc {
+            return Err(Zoom65Error::VerifyMismatch);
+        }
         Ok(())
     }
 
@@ -272,16 +392,52 @@ impl Zoom65v3 {
         self.upload_media(buf, 2)
     }
 
+    /// Transcode and upload an arbitrary image file (PNG/JPEG/WebP/...), resizing and
+    /// center-cropping it to the dimensions `upload_image` expects
+    pub fn upload_image_path(&mut self, path: &Path) -> Result<(), Zoom65Error> {
+        let img = media::decode_fit(path, media::IMAGE_SIZE)?;
+        if self.preview {
+            let _ = preview::preview_rgba(img.to_rgba8().as_raw(), media::IMAGE_SIZE, media::IMAGE_SIZE);
+        }
+        self.upload_image(media::encode_frame(&img))
+    }
+
+    /// Transcode and upload an arbitrary gif file, resizing and center-cropping every frame
+    /// to the dimensions `upload_gif` expects. The preview, if enabled, shows only the first
+    /// frame as a representative thumbnail rather than animating in the terminal.
+    pub fn upload_gif_path(&mut self, path: &Path) -> Result<(), Zoom65Error> {
+        if self.preview {
+            let first_frame = media::decode_fit(path, media::GIF_SIZE)?;
+            let _ = preview::preview_rgba(first_frame.to_rgba8().as_raw(), media::GIF_SIZE, media::GIF_SIZE);
+        }
+        self.upload_gif(media::encode_gif(path)?)
+    }
+
+    /// Start a live screencast stream, capturing `region` at `fps` and pushing downscaled,
+    /// deduplicated frames to the image slot until the returned handle is cancelled. Consumes
+    /// `self` for the stream's lifetime; call `StreamHandle::join` to get the device back.
+    ///
+    /// No built-in capture backend ships yet, so callers must supply their own
+    /// [`capture::CaptureBackend`] implementation.
+    pub fn stream_with_backend(
+        self,
+        backend: impl capture::CaptureBackend + Send + 'static,
+        fps: u32,
+        region: capture::Region,
+    ) -> stream::StreamHandle {
+        stream::stream(self, backend, fps, region)
+    }
+
     /// Clear the image slot
     #[inline(always)]
     pub fn clear_image(&mut self) -> Result<(), Zoom65Error> {
-        self.update(commands::ZOOM65_IMAGE_DELETE, &[]).map(|_| ())
+        self.update(self.profile.image_delete, &[]).map(|_| ())
     }
 
     /// Clear the gif slot
     #[inline(always)]
     pub fn clear_gif(&mut self) -> Result<(), Zoom65Error> {
-        self.update(commands::ZOOM65_GIF_DELETE, &[]).map(|_| ())
+        self.update(self.profile.gif_delete, &[]).map(|_| ())
     }
 }
 
@@ -0,0 +1,111 @@
+//! Transcoding of ordinary image/gif files into the raw pixel layouts the
+//! firmware expects. Kept independent of the HID layer so the encode step
+//! can be exercised without a device attached.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use image::codecs::gif::GifDecoder;
+use image::{AnimationDecoder, DynamicImage, GenericImageView, imageops::FilterType};
+
+use crate::types::Zoom65Error;
+
+/// Target dimensions for the static image slot
+pub(crate) const IMAGE_SIZE: u32 = 110;
+/// Target dimensions for the gif slot
+pub(crate) const GIF_SIZE: u32 = 111;
+/// Maximum encoded gif payload accepted by the firmware
+const MAX_GIF_BYTES: usize = 1013808;
+
+/// Resize and center-crop `img` to a `size`x`size` square
+pub(crate) fn fit_square(img: &DynamicImage, size: u32) -> DynamicImage {
+    let (w, h) = img.dimensions();
+    let scale = size as f32 / w.min(h) as f32;
+    let rw = ((w as f32 * scale).round() as u32).max(size);
+    let rh = ((h as f32 * scale).round() as u32).max(size);
+
+    let resized = img.resize_exact(rw, rh, FilterType::Lanczos3);
+    resized.crop_imm((rw - size) / 2, (rh - size) / 2, size, size)
+}
+
+/// Decode a source file and fit it to a `size`x`size` square, ready for preview or encoding
+pub(crate) fn decode_fit(path: &Path, size: u32) -> Result<DynamicImage, Zoom65Error> {
+    let img = image::open(path).map_err(Zoom65Error::ImageDecode)?;
+    Ok(fit_square(&img, size))
+}
+
+/// Pack a `size`x`size` RGBA frame into the firmware's 2-byte-per-pixel RGBA-3328 layout
+/// (3 bits red, 3 bits green, 2 bits blue packed into one byte, followed by a full alpha byte)
+pub(crate) fn encode_frame(img: &DynamicImage) -> Vec<u8> {
+    let frame = img.to_rgba8();
+    let mut out = Vec::with_capacity(frame.pixels().len() * 2);
+    for pixel in frame.pixels() {
+        let [r, g, b, a] = pixel.0;
+        out.push((r & 0b1110_0000) | ((g & 0b1110_0000) >> 3) | (b >> 6));
+        out.push(a);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{Rgba, RgbaImage};
+
+    use super::*;
+
+    #[test]
+    fn fit_square_crops_to_exact_target_size() {
+        let img = DynamicImage::ImageRgba8(RgbaImage::from_pixel(200, 100, Rgba([1, 2, 3, 4])));
+        let fitted = fit_square(&img, IMAGE_SIZE);
+        assert_eq!(fitted.dimensions(), (IMAGE_SIZE, IMAGE_SIZE));
+    }
+
+    #[test]
+    fn fit_square_upscales_smaller_images() {
+        let img = DynamicImage::ImageRgba8(RgbaImage::from_pixel(32, 32, Rgba([0, 0, 0, 0])));
+        let fitted = fit_square(&img, IMAGE_SIZE);
+        assert_eq!(fitted.dimensions(), (IMAGE_SIZE, IMAGE_SIZE));
+    }
+
+    #[test]
+    fn encode_frame_is_two_bytes_per_pixel() {
+        let img = DynamicImage::ImageRgba8(RgbaImage::from_pixel(IMAGE_SIZE, IMAGE_SIZE, Rgba([0, 0, 0, 0])));
+        let encoded = encode_frame(&img);
+        assert_eq!(encoded.len(), (IMAGE_SIZE * IMAGE_SIZE * 2) as usize);
+    }
+
+    #[test]
+    fn encode_frame_packs_rgb332_plus_alpha() {
+        let img = DynamicImage::ImageRgba8(RgbaImage::from_pixel(1, 1, Rgba([0b1110_0000, 0b0010_0000, 0b1100_0000, 42])));
+        let encoded = encode_frame(&img);
+        assert_eq!(encoded, vec![0b1110_0111, 42]);
+    }
+}
+
+/// Decode an arbitrary image file and encode it into the raw layout `upload_image` expects
+pub fn encode_image(path: &Path) -> Result<Vec<u8>, Zoom65Error> {
+    Ok(encode_frame(&decode_fit(path, IMAGE_SIZE)?))
+}
+
+/// Decode an arbitrary gif file and encode every frame into the raw layout `upload_gif`
+/// expects, dropping trailing frames if the encoded payload would exceed the firmware's
+/// storage limit rather than failing the whole upload.
+pub fn encode_gif(path: &Path) -> Result<Vec<u8>, Zoom65Error> {
+    let file = File::open(path).map_err(Zoom65Error::Io)?;
+    let decoder = GifDecoder::new(BufReader::new(file)).map_err(Zoom65Error::ImageDecode)?;
+
+    let mut out = Vec::new();
+    for frame in decoder.into_frames() {
+        let frame = frame.map_err(Zoom65Error::ImageDecode)?;
+        let square = fit_square(&DynamicImage::ImageRgba8(frame.into_buffer()), GIF_SIZE);
+        let encoded = encode_frame(&square);
+
+        if out.len() + encoded.len() > MAX_GIF_BYTES {
+            break;
+        }
+        out.extend_from_slice(&encoded);
+    }
+
+    Ok(out)
+}
@@ -0,0 +1,113 @@
+//! Periodic aggregated metrics, modeled on crosvm's periodic logger: a
+//! background thread wakes on a fixed tick, drains counters accumulated
+//! since the last tick, and emits a single summary line, instead of the
+//! per-chunk progress prints `upload_media` used to spam stdout with.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Cheap, lock-free counters updated on the upload hot path
+#[derive(Default)]
+pub struct MetricsAccumulator {
+    uploads: AtomicU64,
+    bytes: AtomicU64,
+    failures: AtomicU64,
+    retries: AtomicU64,
+    min_bytes: AtomicU64,
+    max_bytes: AtomicU64,
+}
+
+/// A snapshot of [`MetricsAccumulator`], each counter taken and reset with an atomic swap
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MetricsSnapshot {
+    pub uploads: u64,
+    pub bytes: u64,
+    pub failures: u64,
+    pub retries: u64,
+    pub min_bytes: u64,
+    pub max_bytes: u64,
+}
+
+impl MetricsAccumulator {
+    /// Record one fully completed upload of `bytes` total size
+    pub fn record_upload(&self, bytes: u64) {
+        self.uploads.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+        self.min_bytes.fetch_min(bytes, Ordering::Relaxed);
+        self.max_bytes.fetch_max(bytes, Ordering::Relaxed);
+    }
+
+    /// Record a chunk that was retried after a rejected response
+    pub fn record_retry(&self) {
+        self.retries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a failed operation: an upload abandoned after exhausting its retries, or a
+    /// rejected command or capture
+    pub fn record_failure(&self) {
+        self.failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Take a snapshot of the counters and reset them for the next interval. Each counter is
+    /// swapped atomically, so no update is lost, but the fields are read one after another
+    /// rather than as one consistent set
+    fn take(&self) -> MetricsSnapshot {
+        let min_bytes = self.min_bytes.swap(u64::MAX, Ordering::Relaxed);
+        MetricsSnapshot {
+            uploads: self.uploads.swap(0, Ordering::Relaxed),
+            bytes: self.bytes.swap(0, Ordering::Relaxed),
+            failures: self.failures.swap(0, Ordering::Relaxed),
+            retries: self.retries.swap(0, Ordering::Relaxed),
+            min_bytes: if min_bytes == u64::MAX { 0 } else { min_bytes },
+            max_bytes: self.max_bytes.swap(0, Ordering::Relaxed),
+        }
+    }
+}
+
+/// Shared metrics accumulator for the whole process, mirroring the [`crate::API`] lazy handle
+pub static METRICS: LazyLock<MetricsAccumulator> = LazyLock::new(|| MetricsAccumulator {
+    min_bytes: AtomicU64::new(u64::MAX),
+    ..Default::default()
+});
+
+/// Handle for a running periodic logger thread; stops the thread on drop
+pub struct LoggerHandle {
+    stop: Arc<std::sync::atomic::AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for LoggerHandle {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Spawn a background thread that wakes every `interval`, drains [`METRICS`], and emits one
+/// summary line for the period rather than per-chunk progress output. Periods with no uploads,
+/// failures or retries print nothing
+pub fn spawn_logger(interval: Duration) -> LoggerHandle {
+    let stop = Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let stop_clone = stop.clone();
+
+    let thread = thread::spawn(move || {
+        while !stop_clone.load(Ordering::Relaxed) {
+            thread::sleep(interval);
+            let snap = METRICS.take();
+            if snap.uploads == 0 && snap.failures == 0 && snap.retries == 0 {
+                continue;
+            }
+            let avg_bytes = snap.bytes.checked_div(snap.uploads).unwrap_or(0);
+            println!(
+                "uploads={} bytes={} min={} max={} avg={} failures={} retries={}",
+                snap.uploads, snap.bytes, snap.min_bytes, snap.max_bytes, avg_bytes, snap.failures, snap.retries,
+            );
+        }
+    });
+
+    LoggerHandle { stop, thread: Some(thread) }
+}
@@ -0,0 +1,45 @@
+//! Optional terminal preview of the frame being pushed, via the kitty
+//! graphics protocol, so a user can see on screen what will land on the
+//! keyboard without spawning an external viewer.
+
+use std::io::{self, Write};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+
+/// Maximum size of a single base64-encoded escape sequence chunk
+const CHUNK_SIZE: usize = 4096;
+
+/// Whether the current terminal advertises kitty graphics protocol support
+pub fn supports_kitty() -> bool {
+    std::env::var_os("KITTY_WINDOW_ID").is_some()
+        || std::env::var("TERM")
+            .map(|term| term.contains("kitty"))
+            .unwrap_or(false)
+}
+
+/// Render an RGBA buffer of the given dimensions to the terminal using the kitty graphics
+/// protocol. No-ops if the terminal does not advertise kitty support.
+pub fn preview_rgba(rgba: &[u8], width: u32, height: u32) -> io::Result<()> {
+    if !supports_kitty() {
+        return Ok(());
+    }
+
+    let encoded = BASE64.encode(rgba);
+    let chunks: Vec<&[u8]> = encoded.as_bytes().chunks(CHUNK_SIZE).collect();
+    let mut stdout = io::stdout();
+
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = if i + 1 < chunks.len() { 1 } else { 0 };
+        if i == 0 {
+            // a=T transmits and displays the image; the default a=t would only store it
+            write!(stdout, "\x1b_Ga=T,f=32,s={width},v={height},m={more};")?;
+        } else {
+            write!(stdout, "\x1b_Gm={more};")?;
+        }
+        stdout.write_all(chunk)?;
+        write!(stdout, "\x1b\\")?;
+    }
+
+    stdout.flush()
+}
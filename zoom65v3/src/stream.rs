@@ -0,0 +1,95 @@
+//! Live screencast streaming: continuously capture a screen region, downscale
+//! it to the image slot's dimensions, and push it to the keyboard, turning
+//! the screen into a mini live monitor rather than a one-shot upload.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use image::{DynamicImage, ImageBuffer, Rgba};
+
+use crate::capture::{CaptureBackend, Region};
+use crate::media::{IMAGE_SIZE, encode_frame, fit_square};
+use crate::metrics;
+use crate::types::Zoom65Error;
+use crate::Zoom65v3;
+
+/// Handle used to stop a running stream and wait for its thread to exit
+pub struct StreamHandle {
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<Result<Zoom65v3, Zoom65Error>>,
+}
+
+impl StreamHandle {
+    /// Signal the stream loop to stop
+    pub fn cancel(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    /// Wait for the stream to stop, returning the device for further use
+    pub fn join(self) -> Result<Zoom65v3, Zoom65Error> {
+        self.thread.join().unwrap_or(Err(Zoom65Error::UpdateCommandFailed))
+    }
+}
+
+/// Downscale a captured frame to the image slot's fixed dimensions, center-cropping rather than
+/// stretching so a non-square capture `Region` isn't squished relative to a one-shot upload
+/// (which goes through this same [`fit_square`] path in `media.rs`)
+fn downscale(frame: &crate::capture::Frame) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+    let buf = ImageBuffer::<Rgba<u8>, _>::from_raw(frame.width, frame.height, frame.rgba.clone())
+        .expect("capture backend returned a buffer that doesn't match its own dimensions");
+    fit_square(&DynamicImage::ImageRgba8(buf), IMAGE_SIZE).to_rgba8()
+}
+
+/// Stream `region` to the image slot at `fps`, consuming the device for the lifetime of the
+/// stream. Consecutive unchanged frames (after downscaling) are skipped to stay within HID
+/// bandwidth. Call [`StreamHandle::cancel`] and [`StreamHandle::join`] to stop and get the
+/// device back.
+pub fn stream(
+    mut device: Zoom65v3,
+    mut backend: impl CaptureBackend + Send + 'static,
+    fps: u32,
+    region: Region,
+) -> StreamHandle {
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_clone = stop.clone();
+    let frame_time = Duration::from_secs_f64(1.0 / fps.max(1) as f64);
+
+    let thread = thread::spawn(move || {
+        let mut last: Option<Vec<u8>> = None;
+
+        while !stop_clone.load(Ordering::Relaxed) {
+            let tick = std::time::Instant::now();
+
+            // A transient capture or upload failure shouldn't tear down the whole stream (and
+            // with it, the device `StreamHandle::join` promises to hand back) — record it and
+            // keep going, the same way `daemon.rs` tolerates a rejected per-field command.
+            match backend.capture(region) {
+                Ok(frame) => {
+                    let downscaled = downscale(&frame).into_raw();
+
+                    if last.as_deref() != Some(downscaled.as_slice()) {
+                        let encoded = encode_frame(&image::DynamicImage::ImageRgba8(
+                            ImageBuffer::from_raw(IMAGE_SIZE, IMAGE_SIZE, downscaled.clone()).unwrap(),
+                        ));
+                        if device.upload_image(encoded).is_ok() {
+                            last = Some(downscaled);
+                        } else {
+                            metrics::METRICS.record_failure();
+                        }
+                    }
+                },
+                Err(_) => metrics::METRICS.record_failure(),
+            }
+
+            if let Some(remaining) = frame_time.checked_sub(tick.elapsed()) {
+                thread::sleep(remaining);
+            }
+        }
+
+        Ok(device)
+    });
+
+    StreamHandle { stop, thread }
+}
@@ -0,0 +1,73 @@
+//! Shared types used across the zoom65v3 API
+
+use std::fmt;
+
+/// Errors that can occur while interacting with a zoom65v3 device
+#[derive(Debug)]
+pub enum Zoom65Error {
+    /// No matching HID device was found
+    DeviceNotFound,
+    /// The device reported a firmware version this crate does not recognize
+    UnknownFirmwareVersion,
+    /// An update command was rejected by the device
+    UpdateCommandFailed,
+    /// The provided image/gif payload exceeds the device's storage limit
+    ImageTooLarge,
+    /// The device's stored length/checksum didn't match what was uploaded
+    VerifyMismatch,
+    /// Underlying HID transport error
+    Hid(hidapi::HidError),
+    /// Failed to decode or re-encode a source image/gif file
+    ImageDecode(image::ImageError),
+    /// Failed to read a source image/gif file from disk
+    Io(std::io::Error),
+}
+
+impl fmt::Display for Zoom65Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DeviceNotFound => write!(f, "no zoom65 v3 device found"),
+            Self::UnknownFirmwareVersion => write!(f, "unknown or unsupported firmware version"),
+            Self::UpdateCommandFailed => write!(f, "update command was rejected by the device"),
+            Self::ImageTooLarge => write!(f, "image payload exceeds the device's storage limit"),
+            Self::VerifyMismatch => write!(f, "device's stored length/checksum didn't match the upload"),
+            Self::Hid(e) => write!(f, "hid error: {e}"),
+            Self::ImageDecode(e) => write!(f, "failed to decode image: {e}"),
+            Self::Io(e) => write!(f, "failed to read file: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for Zoom65Error {}
+
+impl From<hidapi::HidError> for Zoom65Error {
+    fn from(e: hidapi::HidError) -> Self {
+        Self::Hid(e)
+    }
+}
+
+/// Weather icon shown alongside the current conditions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Icon {
+    Sunny,
+    Cloudy,
+    Rainy,
+    Snowy,
+    Thunder,
+}
+
+/// Logical position of the screen within its rotation, relative to the default position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScreenPosition {
+    /// Vertical steps, negative is up and positive is down
+    pub row: i8,
+    /// Horizontal steps to switch across from the default offset
+    pub offset: u8,
+}
+
+impl ScreenPosition {
+    /// Decompose into (vertical, horizontal) step counts used by `set_screen`
+    pub fn to_directions(&self) -> (i8, u8) {
+        (self.row, self.offset)
+    }
+}